@@ -3,16 +3,31 @@ use std::time::Duration;
 ///////////////
 // ENDPOINTS //
 ///////////////
-pub(crate) const ENDPOINT_CORE_JWKS: &str = ".well-known/jwks.json";
+pub(crate) const ENDPOINT_OIDC_DISCOVERY: &str = ".well-known/openid-configuration";
 pub(crate) const ENDPOINT_CORE_API_VERSION: &str = "apiversion";
 pub(crate) const ENDPOINT_JWT: &str = "recipe/jwt";
 pub(crate) const ENDPOINT_RECIPE_SIGNIN: &str = "recipe/signin";
+pub(crate) const ENDPOINT_SESSION: &str = "recipe/session";
+pub(crate) const ENDPOINT_SESSION_REFRESH: &str = "recipe/session/refresh";
+pub(crate) const ENDPOINT_SESSION_REMOVE: &str = "recipe/session/remove";
 
 ////////////////
 //   VALUES   //
 ////////////////
 pub(crate) const DEFAULT_JWT_EXPIRATION_TIME: Duration = Duration::new(86400, 0);
 
+/// Allowed clock skew when validating `exp`/`nbf`/`iat` claims during local JWT verification.
+pub(crate) const JWT_CLAIMS_LEEWAY: Duration = Duration::new(60, 0);
+
+/// Default time a `JwksCache` trusts its last fetched `Jwks` before refreshing.
+pub(crate) const DEFAULT_JWKS_CACHE_TTL: Duration = Duration::new(600, 0);
+
+/// Default time a `DiscoveryCache` trusts its last fetched `OidcConfig` before refreshing.
+pub(crate) const DEFAULT_DISCOVERY_CACHE_TTL: Duration = Duration::new(600, 0);
+
+/// Size, in bytes, of the CSPRNG output a refresh token is generated from.
+pub(crate) const REFRESH_TOKEN_BYTES: usize = 64;
+
 /////////////////
 // IDENTIFIERS //
 /////////////////
@@ -0,0 +1,68 @@
+use thiserror::Error as ThisError;
+
+/// Crate-wide error returned by every recipe call, so a malformed core response or a misused
+/// endpoint string is a value the caller can handle instead of a panic that takes their server
+/// down with it.
+#[derive(Debug, ThisError)]
+pub enum Error {
+    /// The request to the SuperTokens core could not be sent, or no response was received.
+    #[error("request to the SuperTokens core failed: {0}")]
+    Network(reqwest::Error),
+
+    /// The core's response body did not match the shape expected for its status code.
+    #[error("failed to deserialize the core's response body: {0}")]
+    Deserialization(reqwest::Error),
+
+    /// The core responded with an HTTP status this call does not know how to handle.
+    #[error("core responded with unexpected status {status}: {body}")]
+    UnexpectedStatus { status: u16, body: String },
+
+    /// An endpoint path passed to `SuperTokens::get_url`/`get_url_with_tenant` was malformed,
+    /// e.g. it started with a leading `/` and would have produced a double slash in the URL.
+    #[error("malformed endpoint path: {0:?}")]
+    MalformedEndpoint(String),
+
+    /// The core responded with HTTP 200 but its own `status` field was not `"OK"`.
+    #[error("core responded with a non-OK status: {0}")]
+    CoreNonOk(String),
+
+    /// The core responded with a `status` of `"OK"` but omitted a field this call expects in
+    /// that case.
+    #[error("core responded with status OK but was missing an expected field")]
+    MalformedResponse,
+
+    /// A `JwksCache` lookup found no key matching the requested `kid`, even after a forced
+    /// refresh. The core's response was well-formed; the key just isn't (or isn't yet) in it.
+    #[error("no key found for the requested kid, even after refreshing the JWKS")]
+    KeyNotFound,
+
+    /// The core rejected the refresh token or session handle (core status `"UNAUTHORISED"`),
+    /// because the session it names was already invalidated, e.g. by a prior refresh rotation
+    /// or revoke. Callers should treat this as "the session is gone, start a new login" rather
+    /// than retrying.
+    #[error("core rejected the request: the session is no longer valid")]
+    UnauthorizedSession,
+
+    /// `SuperTokens::api_key` contains bytes that aren't valid in an HTTP header value.
+    #[error("api_key is not a valid header value: {0}")]
+    InvalidApiKey(reqwest::header::InvalidHeaderValue),
+
+    /// `SuperTokens::cdi_version` contains bytes that aren't valid in an HTTP header value.
+    #[error("cdi_version is not a valid header value: {0}")]
+    InvalidCdiVersion(reqwest::header::InvalidHeaderValue),
+}
+
+/// Returns `resp` unchanged if the core answered with HTTP 200, otherwise consumes it into
+/// `Error::UnexpectedStatus` so every call site handles a non-OK status the same way.
+pub(crate) async fn ensure_ok(resp: reqwest::Response) -> Result<reqwest::Response, Error> {
+    let status = resp.status();
+    if status != reqwest::StatusCode::OK {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(Error::UnexpectedStatus {
+            status: status.as_u16(),
+            body,
+        });
+    }
+
+    Ok(resp)
+}
@@ -1,9 +1,13 @@
 use reqwest::header::{HeaderMap, HeaderValue};
+use thiserror::Error as ThisError;
 
 pub(crate) mod constants;
+mod error;
 pub mod recipe;
 pub mod roles;
 
+pub use error::Error;
+
 /// This is the API exposed by the SuperTokens Core. To be consumed by a backend only.
 pub struct SuperTokens {
     /// Application ID
@@ -41,6 +45,11 @@ pub struct SuperTokens {
     /// assert_eq!(SuperTokens::default().cdi_version, "4.0");
     /// ```
     pub cdi_version: String,
+
+    /// The `reqwest::Client` shared by every recipe call made through this instance, so
+    /// connection pooling, TLS session resumption and any transport configuration set on
+    /// [`SuperTokensBuilder`] are reused instead of rebuilt per request.
+    pub(crate) client: reqwest::Client,
 }
 
 impl Default for SuperTokens {
@@ -48,13 +57,9 @@ impl Default for SuperTokens {
     ///
     /// [Docs](https://app.swaggerhub.com/apis/supertokens/CDI/4.0.2#/)
     fn default() -> Self {
-        Self {
-            app_id: "public".to_string(),
-            tenant_id: "public".to_string(),
-            core_domain: "".to_string(),
-            api_key: "".to_string(),
-            cdi_version: "4.0".to_string(),
-        }
+        SuperTokensBuilder::default()
+            .build()
+            .expect("Default client configuration is always valid")
     }
 }
 
@@ -62,66 +67,193 @@ impl SuperTokens {
     /// Returns the full path to the API endpoint url, using relevant config data
     ///
     /// *example*
-    /// ```
-    /// use supertokens_rust::SuperTokens;
-    ///
-    /// let super_tokens = SuperTokens::new();
-    /// let url = super_tokens.get_url("/recipe/user/metadata");
+    /// ```ignore
+    /// // `get_url` is pub(crate), so this can't be run as a doctest; see the unit test below.
+    /// let url = super_tokens.get_url("recipe/user/metadata").unwrap();
     /// // prints "localhost:8080/appid-public/recipe/user/metadata";
     /// ```
-    pub(crate) fn get_url(&self, endpoint: &str) -> String {
+    pub(crate) fn get_url(&self, endpoint: &str) -> Result<String, Error> {
         // Make sure that we don't end up with double / in the url
-        // TODO ask Jonathan for macro to catch this at compile time :)
-        assert_ne!(endpoint.to_owned().chars().next().unwrap(), '/');
-        format!("{}/appid-{}/{}", self.core_domain, self.app_id, endpoint)
+        if endpoint.starts_with('/') {
+            return Err(Error::MalformedEndpoint(endpoint.to_string()));
+        }
+        Ok(format!(
+            "{}/appid-{}/{}",
+            self.core_domain, self.app_id, endpoint
+        ))
     }
 
     /// Returns the full path to the API endpoint url, using relevant config data
     /// Includes the `tenant_id` url parameter
     ///
     /// *example*
+    /// ```ignore
+    /// // `get_url_with_tenant` is pub(crate), so this can't be run as a doctest; see the unit
+    /// // test below.
+    /// let url = super_tokens.get_url_with_tenant("recipe/user/metadata").unwrap();
+    /// // prints "localhost:8080/appid-public/public/recipe/user/metadata";
     /// ```
-    /// use supertokens_rust::SuperTokens;
-    ///
-    /// let super_tokens = SuperTokens::default();
-    /// let url = super_tokens.get_url_with_tenant("/recipe/user/metadata");
-    /// assert_eq!(url, "/appid-public/public/recipe/user/metadata");
-    /// ```
-    pub(crate) fn get_url_with_tenant(&self, endpoint: &str) -> String {
+    pub(crate) fn get_url_with_tenant(&self, endpoint: &str) -> Result<String, Error> {
         // Make sure that we don't end up with double / in the url
-        // TODO ask Jonathan for macro to catch this at compile time :)
-        assert_ne!(endpoint.to_owned().chars().next().unwrap(), '/');
-        format!(
+        if endpoint.starts_with('/') {
+            return Err(Error::MalformedEndpoint(endpoint.to_string()));
+        }
+        Ok(format!(
             "{}/appid-{}/{}/{}",
             self.core_domain, self.app_id, self.tenant_id, endpoint
-        )
+        ))
     }
 
     /// Returns the headers relevant for the given recipe
     ///
     /// Creates a `reqwest::HeaderMap` consisting of a recipe_id, api_key and cdi_version
     ///
+    /// Fails if `api_key` or `cdi_version` contain bytes that aren't valid in an HTTP header
+    /// value (e.g. a stray newline picked up from an env var) — both are caller-supplied and
+    /// this is called on every recipe request, so that can't be an `.expect()`.
+    ///
     /// *Example*
+    /// ```ignore
+    /// // `get_headers` is pub(crate), so this can't be run as a doctest.
+    /// let headers = st.get_headers(Some(Recipe::EmailPassword))?;
     /// ```
-    /// use supertokens_rust::{Recipe, SuperTokens};
-    /// let st = SuperTokens::default();
-    /// let headers = st.get_headers(Some(Recipe::EmailPassword));
-    /// ```
-    pub(crate) fn get_headers(&self, recipe: Option<Recipe>) -> HeaderMap {
+    pub(crate) fn get_headers(&self, recipe: Option<Recipe>) -> Result<HeaderMap, Error> {
         let mut headers = HeaderMap::new();
 
         if let Some(recipe) = recipe {
             headers.insert("rid", recipe.into());
         }
 
-        let api_key = HeaderValue::from_str(&self.api_key).expect("Should be valid");
+        let api_key = HeaderValue::from_str(&self.api_key).map_err(Error::InvalidApiKey)?;
         headers.insert("api-key", api_key);
 
-        let cdi_version = HeaderValue::from_str(&self.cdi_version).expect("Should be valid");
+        let cdi_version =
+            HeaderValue::from_str(&self.cdi_version).map_err(Error::InvalidCdiVersion)?;
         headers.insert("cdi-version", cdi_version);
 
-        headers
+        Ok(headers)
+    }
+}
+
+/// Builds a [`SuperTokens`], letting the underlying `reqwest::Client` be configured before any
+/// recipe call is made.
+///
+/// *Example*
+/// ```
+/// use std::time::Duration;
+/// use supertokens_rust::SuperTokensBuilder;
+///
+/// let st = SuperTokensBuilder::default()
+///     .core_domain("http://localhost:3567")
+///     .api_key("my-api-key")
+///     .timeout(Duration::from_secs(10))
+///     .build()
+///     .expect("Valid client configuration");
+/// ```
+pub struct SuperTokensBuilder {
+    app_id: String,
+    tenant_id: String,
+    core_domain: String,
+    api_key: String,
+    cdi_version: String,
+    client_builder: reqwest::ClientBuilder,
+}
+
+impl Default for SuperTokensBuilder {
+    /// Provides the same defaults as [`SuperTokens::default`], with an unconfigured client.
+    fn default() -> Self {
+        Self {
+            app_id: "public".to_string(),
+            tenant_id: "public".to_string(),
+            core_domain: "".to_string(),
+            api_key: "".to_string(),
+            cdi_version: "4.0".to_string(),
+            client_builder: reqwest::Client::builder(),
+        }
+    }
+}
+
+impl SuperTokensBuilder {
+    /// Sets the application ID, default : `"public"`
+    pub fn app_id(mut self, app_id: impl Into<String>) -> Self {
+        self.app_id = app_id.into();
+        self
+    }
+
+    /// Sets the tenant ID, default : `"public"`
+    pub fn tenant_id(mut self, tenant_id: impl Into<String>) -> Self {
+        self.tenant_id = tenant_id.into();
+        self
+    }
+
+    /// Sets the url domain for the SuperTokens Core instance
+    pub fn core_domain(mut self, core_domain: impl Into<String>) -> Self {
+        self.core_domain = core_domain.into();
+        self
+    }
+
+    /// Sets the authentication API-Key
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = api_key.into();
+        self
+    }
+
+    /// Sets the Contexts and Dependency Injection for Java version, default : `"4.0"`
+    pub fn cdi_version(mut self, cdi_version: impl Into<String>) -> Self {
+        self.cdi_version = cdi_version.into();
+        self
+    }
+
+    /// Sets the request timeout applied to every call made through the built client.
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.client_builder = self.client_builder.timeout(timeout);
+        self
+    }
+
+    /// Sets headers sent with every request made through the built client, in addition to the
+    /// recipe/api-key/cdi-version headers added per-call by [`SuperTokens::get_headers`].
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.client_builder = self.client_builder.default_headers(headers);
+        self
+    }
+
+    /// Routes every request made through the built client through `proxy`.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.client_builder = self.client_builder.proxy(proxy);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request made through the built client.
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.client_builder = self.client_builder.user_agent(user_agent.into());
+        self
     }
+
+    /// Builds the `SuperTokens` instance, failing only if the underlying `reqwest::Client`
+    /// could not be constructed from the given configuration (e.g. an invalid TLS backend).
+    pub fn build(self) -> Result<SuperTokens, SuperTokensBuilderError> {
+        let client = self
+            .client_builder
+            .build()
+            .map_err(SuperTokensBuilderError::Client)?;
+
+        Ok(SuperTokens {
+            app_id: self.app_id,
+            tenant_id: self.tenant_id,
+            core_domain: self.core_domain,
+            api_key: self.api_key,
+            cdi_version: self.cdi_version,
+            client,
+        })
+    }
+}
+
+/// Possible error states when building a `SuperTokens` via `SuperTokensBuilder`.
+#[derive(Debug, ThisError)]
+pub enum SuperTokensBuilderError {
+    /// The underlying `reqwest::Client` could not be constructed.
+    #[error("failed to build the underlying reqwest client: {0}")]
+    Client(reqwest::Error),
 }
 
 pub(crate) enum Recipe {
@@ -129,6 +261,7 @@ pub(crate) enum Recipe {
     PasswordLess,
     ThirdParty,
     Jwt,
+    Session,
 }
 
 impl From<Recipe> for HeaderValue {
@@ -138,7 +271,51 @@ impl From<Recipe> for HeaderValue {
             Recipe::PasswordLess => "passwordless",
             Recipe::ThirdParty => "thirdparty",
             Recipe::Jwt => "jwt",
+            Recipe::Session => "session",
         };
         HeaderValue::from_str(rid).expect("Should be valid")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn super_tokens() -> SuperTokens {
+        SuperTokensBuilder::default()
+            .core_domain("http://localhost:3567")
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn get_url_joins_core_domain_app_id_and_endpoint() {
+        let st = super_tokens();
+        let url = st.get_url("recipe/user/metadata").unwrap();
+        assert_eq!(url, "http://localhost:3567/appid-public/recipe/user/metadata");
+    }
+
+    #[test]
+    fn get_url_rejects_an_endpoint_with_a_leading_slash() {
+        let st = super_tokens();
+        let err = st.get_url("/recipe/user/metadata").unwrap_err();
+        assert!(matches!(err, Error::MalformedEndpoint(_)));
+    }
+
+    #[test]
+    fn get_url_with_tenant_joins_core_domain_app_id_tenant_id_and_endpoint() {
+        let st = super_tokens();
+        let url = st.get_url_with_tenant("recipe/user/metadata").unwrap();
+        assert_eq!(
+            url,
+            "http://localhost:3567/appid-public/public/recipe/user/metadata"
+        );
+    }
+
+    #[test]
+    fn get_url_with_tenant_rejects_an_endpoint_with_a_leading_slash() {
+        let st = super_tokens();
+        let err = st.get_url_with_tenant("/recipe/user/metadata").unwrap_err();
+        assert!(matches!(err, Error::MalformedEndpoint(_)));
+    }
+}
@@ -0,0 +1,210 @@
+use crate::constants::{DEFAULT_DISCOVERY_CACHE_TTL, ENDPOINT_OIDC_DISCOVERY};
+use crate::error::{ensure_ok, Error};
+use crate::SuperTokens;
+use serde::Deserialize;
+use std::time::{Duration, SystemTime};
+
+/// Fetches the OIDC discovery document the SuperTokens core publishes at
+/// `.well-known/openid-configuration`, so the issuer and JWKS endpoint don't need to be
+/// hardcoded or assumed to match `core_domain`.
+///
+/// *Example*
+/// ```no_run
+/// use supertokens_rust::{recipe::discovery, SuperTokens};
+///
+/// # async fn run() {
+/// let st = SuperTokens::default();
+/// let config = discovery::discover(&st).await.unwrap();
+/// println!("issuer: {}", config.issuer);
+/// # }
+/// ```
+pub async fn discover(st: &SuperTokens) -> Result<OidcConfig, Error> {
+    let resp = st
+        .client
+        .get(format!("{}/{}", st.core_domain, ENDPOINT_OIDC_DISCOVERY))
+        .send()
+        .await
+        .map_err(Error::Network)?;
+
+    let resp = ensure_ok(resp).await?;
+
+    resp.json::<OidcConfig>()
+        .await
+        .map_err(Error::Deserialization)
+}
+
+/// OIDC discovery metadata published by the SuperTokens core.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OidcConfig {
+    pub issuer: String,
+    pub jwks_uri: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+}
+
+/// Caches the last fetched `OidcConfig` for `ttl` and transparently refreshes it on expiry, so
+/// callers on a hot path (e.g. minting or verifying a token for every request) don't each pay for
+/// a `.well-known/openid-configuration` round-trip.
+///
+/// Mirrors [`JwksCache`](crate::recipe::jwt::JwksCache)'s design: a cache hit only needs a read
+/// lock, so concurrent lookups don't serialize on each other; only a stale or empty cache promotes
+/// to a write lock, behind which refreshes are single-flighted so concurrent lookups that arrive
+/// while the cache is stale share one [`discover`] call instead of stampeding the core.
+pub struct DiscoveryCache {
+    ttl: Duration,
+    state: tokio::sync::RwLock<DiscoveryCacheState>,
+}
+
+struct DiscoveryCacheState {
+    config: Option<OidcConfig>,
+    fetched_at: Option<SystemTime>,
+}
+
+impl DiscoveryCache {
+    /// Creates an empty cache that refreshes its `OidcConfig` at most every `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            state: tokio::sync::RwLock::new(DiscoveryCacheState {
+                config: None,
+                fetched_at: None,
+            }),
+        }
+    }
+
+    /// Returns the cached `OidcConfig`, refreshing via `st` if the cache is stale or empty.
+    pub async fn config(&self, st: &SuperTokens) -> Result<OidcConfig, Error> {
+        self.get_or_refresh(|| discover(st)).await
+    }
+
+    /// Does the actual cache lookup/refresh, with the fetch itself pulled out behind `fetch` so
+    /// the locking and TTL behavior can be unit tested without a core to talk to.
+    async fn get_or_refresh<F, Fut>(&self, fetch: F) -> Result<OidcConfig, Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<OidcConfig, Error>>,
+    {
+        {
+            let state = self.state.read().await;
+            if Self::is_fresh(state.fetched_at, self.ttl) {
+                if let Some(config) = &state.config {
+                    return Ok(config.clone());
+                }
+            }
+        }
+
+        let mut state = self.state.write().await;
+
+        // Another task may have refreshed the cache while we were waiting for the write lock.
+        if Self::is_fresh(state.fetched_at, self.ttl) {
+            if let Some(config) = &state.config {
+                return Ok(config.clone());
+            }
+        }
+
+        let config = fetch().await?;
+        state.config = Some(config.clone());
+        state.fetched_at = Some(SystemTime::now());
+
+        Ok(config)
+    }
+
+    fn is_fresh(fetched_at: Option<SystemTime>, ttl: Duration) -> bool {
+        fetched_at
+            .map(|fetched_at| fetched_at.elapsed().unwrap_or(ttl) < ttl)
+            .unwrap_or(false)
+    }
+}
+
+impl Default for DiscoveryCache {
+    /// Creates an empty cache using `DEFAULT_DISCOVERY_CACHE_TTL`.
+    fn default() -> Self {
+        Self::new(DEFAULT_DISCOVERY_CACHE_TTL)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn oidc_config(issuer: &str) -> OidcConfig {
+        OidcConfig {
+            issuer: issuer.to_string(),
+            jwks_uri: "http://localhost:3567/.well-known/jwks.json".to_string(),
+            authorization_endpoint: "http://localhost:3567/auth".to_string(),
+            token_endpoint: "http://localhost:3567/token".to_string(),
+            userinfo_endpoint: "http://localhost:3567/userinfo".to_string(),
+        }
+    }
+
+    async fn fetch_and_count(calls: Arc<AtomicUsize>, issuer: &'static str) -> Result<OidcConfig, Error> {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Ok(oidc_config(issuer))
+    }
+
+    #[tokio::test]
+    async fn reuses_a_fresh_cache_without_refetching() {
+        let cache = DiscoveryCache::new(Duration::from_secs(60));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..3 {
+            let calls = calls.clone();
+            let config = cache
+                .get_or_refresh(|| fetch_and_count(calls, "https://issuer-a"))
+                .await
+                .unwrap();
+            assert_eq!(config.issuer, "https://issuer-a");
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn refetches_once_the_cache_is_stale() {
+        let cache = DiscoveryCache::new(Duration::from_millis(10));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        cache
+            .get_or_refresh(|| fetch_and_count(calls.clone(), "https://issuer-a"))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        cache
+            .get_or_refresh(|| fetch_and_count(calls.clone(), "https://issuer-a"))
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn concurrent_lookups_during_a_stale_cache_single_flight_the_refresh() {
+        let cache = Arc::new(DiscoveryCache::new(Duration::from_secs(60)));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..8).map(|_| {
+            let cache = cache.clone();
+            let calls = calls.clone();
+            tokio::spawn(async move {
+                cache
+                    .get_or_refresh(|| async move {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        // Give the other spawned lookups a chance to race for the same lock.
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok(oidc_config("https://issuer-a"))
+                    })
+                    .await
+            })
+        });
+
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::constants::ENDPOINT_RECIPE_SIGNIN;
+use crate::error::{ensure_ok, Error};
 use crate::{Recipe, SuperTokens};
-use reqwest::{Error, StatusCode};
 use serde::{Deserialize, Serialize};
 
 /// Signs a user in using email and password
@@ -15,41 +15,35 @@ pub async fn sign_in(
     st: &SuperTokens,
     email: &str,
     password: &str,
-) -> Result<SignInSuccess, SignInError> {
-    let resp = reqwest::Client::new()
-        .post(st.get_url_with_tenant(ENDPOINT_RECIPE_SIGNIN))
-        .headers(st.get_headers(Some(Recipe::EmailPassword)))
+) -> Result<SignInSuccess, Error> {
+    let resp = st
+        .client
+        .post(st.get_url_with_tenant(ENDPOINT_RECIPE_SIGNIN)?)
+        .headers(st.get_headers(Some(Recipe::EmailPassword))?)
         .json(&SignInRequest {
             email: email.to_string(),
             password: password.to_string(),
         })
         .send()
-        .await?;
+        .await
+        .map_err(Error::Network)?;
 
-    if resp.status() == StatusCode::OK {
-        // check for wrong credentials, as per documentation
-        // (blame the API for returning errors with code 200 *sigh*)
-        let json = resp
-            .json::<SignInResponseRaw>()
-            .await
-            .expect("Invalid JSON struct");
+    let resp = ensure_ok(resp).await?;
 
-        if json.status != "OK" {
-            return Err(SignInError::WrongCredentials);
-        }
+    // check for wrong credentials, as per documentation
+    // (blame the API for returning errors with code 200 *sigh*)
+    let json = resp
+        .json::<SignInResponseRaw>()
+        .await
+        .map_err(Error::Deserialization)?;
 
-        return Ok(SignInSuccess {
-            user_id: json.recipe_user_id.expect("Is valid here"),
-            user: json.user.expect("Is valid here"),
-        });
+    if json.status != "OK" {
+        return Err(Error::CoreNonOk(json.status));
     }
 
-    Err(match resp.status().as_u16() {
-        400 => SignInError::BadRequest(resp.text().await.unwrap_or("Bad Request".to_string())),
-        401 => SignInError::InvalidApiKey,
-        404 => SignInError::NotFound,
-        500 => SignInError::InternalError,
-        _ => SignInError::Unknown,
+    Ok(SignInSuccess {
+        user_id: json.recipe_user_id.ok_or(Error::MalformedResponse)?,
+        user: json.user.ok_or(Error::MalformedResponse)?,
     })
 }
 
@@ -60,22 +54,6 @@ struct SignInRequest {
     pub password: String,
 }
 
-#[derive(Debug)]
-pub enum SignInError {
-    BadRequest(String),
-    WrongCredentials,
-    InvalidApiKey,
-    NotFound,
-    InternalError,
-    Unknown,
-}
-
-impl From<Error> for SignInError {
-    fn from(_value: Error) -> Self {
-        SignInError::Unknown
-    }
-}
-
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignInResponseRaw {
@@ -1,53 +1,67 @@
-use crate::constants::{DEFAULT_JWT_EXPIRATION_TIME, ENDPOINT_CORE_JWKS, ENDPOINT_JWT};
+use crate::constants::{
+    DEFAULT_JWKS_CACHE_TTL, DEFAULT_JWT_EXPIRATION_TIME, ENDPOINT_JWT, JWT_CLAIMS_LEEWAY,
+};
+use crate::error::{ensure_ok, Error};
+use crate::recipe::discovery::DiscoveryCache;
 use crate::{Recipe, SuperTokens};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine as _;
+use rsa::pkcs1v15::{Signature, VerifyingKey};
+use rsa::pkcs8::DecodePublicKey;
+use rsa::signature::Verifier;
+use rsa::RsaPublicKey;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error as ThisError;
+use x509_parser::prelude::{FromDer, X509Certificate};
 
 /// Creates a JWT on behalf of a user and returns it
+///
+/// The `iss` claim is set to the issuer discovered from the core's OIDC discovery document,
+/// rather than assumed to be `st.core_domain`. `discovery` is consulted rather than hitting
+/// `.well-known/openid-configuration` on every call; pass the same cache across calls so it's
+/// actually reused.
 pub async fn create_token<T: Serialize>(
     st: &SuperTokens,
+    discovery: &DiscoveryCache,
     payload: T,
     expiration_time: Option<Duration>,
-) -> Result<String, JwtCreationError> {
+) -> Result<String, Error> {
+    let config = discovery.config(st).await?;
+
     let body = JwtCreationRequest::new(
         payload,
-        &st.core_domain,
+        &config.issuer,
         expiration_time
             .unwrap_or(DEFAULT_JWT_EXPIRATION_TIME)
             .as_secs() as u32,
     );
 
-    let resp = reqwest::Client::new()
-        .post(&st.get_url(ENDPOINT_JWT))
-        .headers(st.get_headers(Some(Recipe::Jwt)))
+    let resp = st
+        .client
+        .post(st.get_url(ENDPOINT_JWT)?)
+        .headers(st.get_headers(Some(Recipe::Jwt))?)
         .json(&body)
         .send()
-        .await;
-
-    if let Err(err) = resp {
-        return match err.status() {
-            None => Err(JwtCreationError::Unknown),
-            Some(status) => match status.as_u16() {
-                400 => Err(JwtCreationError::BadRequest("Bad Request".to_string())),
-                404 => Err(JwtCreationError::NotFound),
-                500 => Err(JwtCreationError::InternalError),
-                _ => Err(JwtCreationError::Unknown),
-            },
-        };
-    }
-    let resp_payload = resp
-        .expect("Error here is not possible")
-        .json::<JwtResponsePayload>()
         .await
-        .expect("Json for status 200");
+        .map_err(Error::Network)?;
+
+    let resp = ensure_ok(resp).await?;
 
     // from docs at https://app.swaggerhub.com/apis/supertokens/CDI/4.0.2#/JWT%20Recipe/createSignedJWT
     // check the status field on the response
+    let resp_payload = resp
+        .json::<JwtResponsePayload>()
+        .await
+        .map_err(Error::Deserialization)?;
 
-    if resp_payload.status == "OK" {
-        return Ok(resp_payload.jwt.expect("Is here since status is OK"));
+    if resp_payload.status != "OK" {
+        return Err(Error::CoreNonOk(resp_payload.status));
     }
-    return Err(JwtCreationError::UnsupportedAlgorithm);
+
+    resp_payload.jwt.ok_or(Error::MalformedResponse)
 }
 
 /// Payload to create a jwt token for a user, with the custom claims type `T`
@@ -86,15 +100,6 @@ impl<T: Serialize> JwtCreationRequest<T> {
     }
 }
 
-#[derive(Debug)]
-pub enum JwtCreationError {
-    BadRequest(String),
-    UnsupportedAlgorithm,
-    NotFound,
-    InternalError,
-    Unknown,
-}
-
 /// Payload returned from calling the JWT api at supertokens_core
 #[derive(Deserialize, Debug)]
 struct JwtResponsePayload {
@@ -111,25 +116,382 @@ struct JwtResponsePayload {
 ///
 /// For more info, see [JWT](https://jwt.io/introduction) or
 /// [JWKS](https://auth0.com/docs/secure/tokens/json-web-tokens/json-web-key-sets)
-pub async fn get_jwks(core_url: &str) -> Result<Jwks, JwksError> {
-    let resp = reqwest::get(core_url.to_owned() + ENDPOINT_CORE_JWKS).await;
-
-    match resp {
-        Ok(r) => match r.json::<Jwks>().await {
-            Ok(v) => Ok(v),
-            Err(_e) => Err(JwksError::ResponseFormat),
-        },
-        Err(e) => {
-            if let Some(s) = e.status() {
-                if s.is_server_error() {
-                    return Err(JwksError::Internal);
+///
+/// The `jwks_uri` to fetch from is found via the core's OIDC discovery document, rather than
+/// assumed to be `st.core_domain` + a hardcoded well-known path. `discovery` is consulted rather
+/// than hitting `.well-known/openid-configuration` on every call; pass the same cache across
+/// calls so it's actually reused.
+pub async fn get_jwks(st: &SuperTokens, discovery: &DiscoveryCache) -> Result<Jwks, Error> {
+    let config = discovery.config(st).await?;
+    fetch_jwks(st, &config.jwks_uri).await
+}
+
+async fn fetch_jwks(st: &SuperTokens, jwks_uri: &str) -> Result<Jwks, Error> {
+    let resp = st
+        .client
+        .get(jwks_uri)
+        .send()
+        .await
+        .map_err(Error::Network)?;
+    let resp = ensure_ok(resp).await?;
+
+    resp.json::<Jwks>().await.map_err(Error::Deserialization)
+}
+
+/// Caches the last fetched `Jwks` for `ttl` and transparently refreshes it on expiry or when a
+/// requested `kid` is missing, which happens right after SuperTokens rotates its dynamic key.
+///
+/// Lookups take a read lock to check freshness and look for `kid`, so concurrent cache hits don't
+/// serialize on each other; only a stale or missing key promotes to a write lock to refresh.
+/// Refreshes are single-flighted behind that write lock, so concurrent lookups that arrive while
+/// the cache is stale share one `get_jwks` call instead of stampeding the core.
+pub struct JwksCache {
+    ttl: Duration,
+    discovery: DiscoveryCache,
+    state: tokio::sync::RwLock<JwksCacheState>,
+}
+
+struct JwksCacheState {
+    jwks: Option<Jwks>,
+    fetched_at: Option<SystemTime>,
+}
+
+impl JwksCache {
+    /// Creates an empty cache that refreshes its `Jwks` at most every `ttl`.
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            discovery: DiscoveryCache::new(ttl),
+            state: tokio::sync::RwLock::new(JwksCacheState {
+                jwks: None,
+                fetched_at: None,
+            }),
+        }
+    }
+
+    /// Looks up the `Jwk` matching `kid`, refreshing via `st` if the cache is stale or the key is
+    /// not present in the currently cached set.
+    pub async fn key_for_kid(&self, st: &SuperTokens, kid: &str) -> Result<Jwk, Error> {
+        let discovery = &self.discovery;
+        self.lookup(kid, || get_jwks(st, discovery)).await
+    }
+
+    /// Does the actual cache lookup/refresh, with the fetch itself pulled out behind `fetch` so
+    /// the locking and TTL behavior can be unit tested without a core to talk to.
+    async fn lookup<F, Fut>(&self, kid: &str, fetch: F) -> Result<Jwk, Error>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<Jwks, Error>>,
+    {
+        {
+            let state = self.state.read().await;
+            if Self::is_fresh(state.fetched_at, self.ttl) {
+                if let Some(key) = Self::find_key(&state.jwks, kid) {
+                    return Ok(key);
                 }
             }
-            Err(JwksError::Unknown)
         }
+
+        let mut state = self.state.write().await;
+
+        // Another task may have refreshed the cache while we were waiting for the write lock.
+        if Self::is_fresh(state.fetched_at, self.ttl) {
+            if let Some(key) = Self::find_key(&state.jwks, kid) {
+                return Ok(key);
+            }
+        }
+
+        let jwks = fetch().await?;
+        let key = Self::find_key(&Some(jwks.clone()), kid);
+
+        state.jwks = Some(jwks);
+        state.fetched_at = Some(SystemTime::now());
+
+        key.ok_or(Error::KeyNotFound)
+    }
+
+    fn is_fresh(fetched_at: Option<SystemTime>, ttl: Duration) -> bool {
+        fetched_at
+            .map(|fetched_at| fetched_at.elapsed().unwrap_or(ttl) < ttl)
+            .unwrap_or(false)
+    }
+
+    fn find_key(jwks: &Option<Jwks>, kid: &str) -> Option<Jwk> {
+        jwks.as_ref()?
+            .keys
+            .iter()
+            .find(|key| key.kid == kid)
+            .cloned()
+    }
+}
+
+impl Default for JwksCache {
+    /// Creates an empty cache using `DEFAULT_JWKS_CACHE_TTL`.
+    fn default() -> Self {
+        Self::new(DEFAULT_JWKS_CACHE_TTL)
+    }
+}
+
+#[cfg(test)]
+mod jwks_cache_tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn jwks_with_kid(kid: &str) -> Jwks {
+        Jwks {
+            keys: vec![Jwk {
+                alg: "RS256".to_string(),
+                kty: "RSA".to_string(),
+                key_use: "sig".to_string(),
+                kid: kid.to_string(),
+                x5c: vec![],
+            }],
+        }
+    }
+
+    async fn fetch_and_count(calls: Arc<AtomicUsize>, kid: &'static str) -> Result<Jwks, Error> {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Ok(jwks_with_kid(kid))
+    }
+
+    #[tokio::test]
+    async fn reuses_a_fresh_cache_without_refetching() {
+        let cache = JwksCache::new(Duration::from_secs(60));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..3 {
+            let calls = calls.clone();
+            let key = cache
+                .lookup("kid-a", || fetch_and_count(calls, "kid-a"))
+                .await
+                .unwrap();
+            assert_eq!(key.kid, "kid-a");
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn refetches_once_the_cache_is_stale() {
+        let cache = JwksCache::new(Duration::from_millis(10));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        cache
+            .lookup("kid-a", || fetch_and_count(calls.clone(), "kid-a"))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        cache
+            .lookup("kid-a", || fetch_and_count(calls.clone(), "kid-a"))
+            .await
+            .unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn refetches_when_the_requested_kid_is_missing() {
+        let cache = JwksCache::new(Duration::from_secs(60));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        cache
+            .lookup("kid-a", || fetch_and_count(calls.clone(), "kid-a"))
+            .await
+            .unwrap();
+        let key = cache
+            .lookup("kid-b", || fetch_and_count(calls.clone(), "kid-b"))
+            .await
+            .unwrap();
+
+        assert_eq!(key.kid, "kid-b");
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn concurrent_lookups_during_a_stale_cache_single_flight_the_refresh() {
+        let cache = Arc::new(JwksCache::new(Duration::from_secs(60)));
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let handles = (0..8).map(|_| {
+            let cache = cache.clone();
+            let calls = calls.clone();
+            tokio::spawn(async move {
+                cache
+                    .lookup("kid-a", || async move {
+                        calls.fetch_add(1, Ordering::SeqCst);
+                        // Give the other spawned lookups a chance to race for the same lock.
+                        tokio::time::sleep(Duration::from_millis(20)).await;
+                        Ok(jwks_with_kid("kid-a"))
+                    })
+                    .await
+            })
+        });
+
+        for handle in handles {
+            handle.await.unwrap().unwrap();
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 }
 
+/// Verifies a compact JWT fully offline against a fetched `Jwks`, and decodes its payload into `T`.
+///
+/// Only the `RS256` algorithm is supported, matching the keys SuperTokens issues. The signature
+/// is checked against the `Jwk` whose `kid` matches the token header before any claim is trusted.
+/// Once the signature is valid, the standard `exp`/`nbf`/`iat` claims are checked with a small
+/// leeway to account for clock skew, and `iss` is checked against `expected_issuer` if given.
+pub async fn verify_token<T: DeserializeOwned>(
+    jwks: &Jwks,
+    token: &str,
+    expected_issuer: Option<&str>,
+) -> Result<T, JwtVerificationError> {
+    let mut parts = token.split('.');
+    let (header_b64, payload_b64, signature_b64) = match (parts.next(), parts.next(), parts.next())
+    {
+        (Some(h), Some(p), Some(s)) if parts.next().is_none() => (h, p, s),
+        _ => return Err(JwtVerificationError::Malformed),
+    };
+
+    let header_bytes = URL_SAFE_NO_PAD
+        .decode(header_b64)
+        .map_err(|_| JwtVerificationError::Malformed)?;
+    let header: JwtHeader =
+        serde_json::from_slice(&header_bytes).map_err(|_| JwtVerificationError::Malformed)?;
+
+    if header.alg != "RS256" {
+        return Err(JwtVerificationError::UnsupportedAlgorithm);
+    }
+
+    let jwk = jwks
+        .keys
+        .iter()
+        .find(|key| key.kid == header.kid)
+        .ok_or(JwtVerificationError::KeyNotFound)?;
+
+    let public_key = rsa_public_key_from_x5c(jwk)?;
+
+    let signature_bytes = URL_SAFE_NO_PAD
+        .decode(signature_b64)
+        .map_err(|_| JwtVerificationError::Malformed)?;
+    let signature = Signature::try_from(signature_bytes.as_slice())
+        .map_err(|_| JwtVerificationError::BadSignature)?;
+
+    let signing_input = format!("{header_b64}.{payload_b64}");
+    VerifyingKey::<Sha256>::new(public_key)
+        .verify(signing_input.as_bytes(), &signature)
+        .map_err(|_| JwtVerificationError::BadSignature)?;
+
+    let payload_bytes = URL_SAFE_NO_PAD
+        .decode(payload_b64)
+        .map_err(|_| JwtVerificationError::Malformed)?;
+    let payload: serde_json::Value =
+        serde_json::from_slice(&payload_bytes).map_err(|_| JwtVerificationError::ClaimsFormat)?;
+    let claims: StandardClaims =
+        serde_json::from_value(payload.clone()).map_err(|_| JwtVerificationError::ClaimsFormat)?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is after the unix epoch")
+        .as_secs() as i64;
+    let leeway = JWT_CLAIMS_LEEWAY.as_secs() as i64;
+
+    if let Some(exp) = claims.exp {
+        if now - leeway > exp {
+            return Err(JwtVerificationError::Expired);
+        }
+    }
+    if let Some(nbf) = claims.nbf {
+        if now + leeway < nbf {
+            return Err(JwtVerificationError::NotYetValid);
+        }
+    }
+    if let Some(iat) = claims.iat {
+        if now + leeway < iat {
+            return Err(JwtVerificationError::NotYetValid);
+        }
+    }
+    if let Some(expected_issuer) = expected_issuer {
+        if claims.iss.as_deref() != Some(expected_issuer) {
+            return Err(JwtVerificationError::IssuerMismatch);
+        }
+    }
+
+    serde_json::from_value(payload).map_err(|_| JwtVerificationError::ClaimsFormat)
+}
+
+/// Builds the RSA public key carried by a `Jwk`'s leading `x5c` certificate.
+fn rsa_public_key_from_x5c(jwk: &Jwk) -> Result<RsaPublicKey, JwtVerificationError> {
+    let cert_der = jwk
+        .x5c
+        .first()
+        .ok_or(JwtVerificationError::Malformed)
+        .and_then(|cert| {
+            STANDARD
+                .decode(cert)
+                .map_err(|_| JwtVerificationError::Malformed)
+        })?;
+
+    let (_, cert) =
+        X509Certificate::from_der(&cert_der).map_err(|_| JwtVerificationError::Malformed)?;
+
+    RsaPublicKey::from_public_key_der(cert.public_key().raw)
+        .map_err(|_| JwtVerificationError::Malformed)
+}
+
+/// The subset of the JWT header relevant to signature verification.
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+    kid: String,
+}
+
+/// The standard claims inspected by `verify_token`, decoded alongside the caller's own payload.
+#[derive(Deserialize)]
+struct StandardClaims {
+    exp: Option<i64>,
+    nbf: Option<i64>,
+    iat: Option<i64>,
+    iss: Option<String>,
+}
+
+/// Possible error states when verifying a JWT against a `Jwks` locally.
+#[derive(Debug, ThisError)]
+pub enum JwtVerificationError {
+    /// No key in the `Jwks` matched the token's `kid` header.
+    #[error("no key in the JWKS matches the token's kid")]
+    KeyNotFound,
+
+    /// The token's `alg` header is not supported; only `RS256` is.
+    #[error("unsupported alg header, only RS256 is accepted")]
+    UnsupportedAlgorithm,
+
+    /// The RS256 signature did not verify against the matched key.
+    #[error("signature does not verify against the matched key")]
+    BadSignature,
+
+    /// The token's `exp` claim is in the past.
+    #[error("token's exp claim is in the past")]
+    Expired,
+
+    /// The token's `nbf` or `iat` claim is in the future.
+    #[error("token's nbf or iat claim is in the future")]
+    NotYetValid,
+
+    /// `expected_issuer` was given and did not match the token's `iss` claim.
+    #[error("token's iss claim does not match the expected issuer")]
+    IssuerMismatch,
+
+    /// The token is not a well-formed `header.payload.signature` compact JWT.
+    #[error("token is not a well-formed header.payload.signature compact JWT")]
+    Malformed,
+
+    /// The payload could not be decoded into the expected claims shape.
+    #[error("token payload does not match the expected claims shape")]
+    ClaimsFormat,
+}
+
 /// Response object after fetching jwks from supertokens_core
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Jwks {
@@ -160,19 +522,136 @@ pub struct Jwk {
     x5c: Vec<String>,
 }
 
-/// Possible error states of fetching jwks from the
-/// super_tokens core api
-#[derive(Debug)]
-pub enum JwksError {
-    NotFound,
-    /// Internal server error
-    /// Code 500 range
-    Internal,
+#[cfg(test)]
+mod verify_token_tests {
+    use super::*;
+    use rand::rngs::OsRng;
+    use rsa::pkcs1v15::SigningKey;
+    use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+    use rsa::signature::{SignatureEncoding, Signer};
+    use rsa::RsaPrivateKey;
+
+    /// Generates a fresh RSA keypair and a self-signed certificate for it, returning the
+    /// private key (to sign test tokens with) and a `Jwks` exposing the certificate under `kid`.
+    ///
+    /// Needs `rcgen` as a dev-dependency to build the certificate; not declared here because
+    /// this checkout has no `Cargo.toml` to add it to.
+    fn test_keypair_and_jwks(kid: &str) -> (RsaPrivateKey, Jwks) {
+        let private_key = RsaPrivateKey::new(&mut OsRng, 2048).expect("generate RSA key");
+        let pem = private_key
+            .to_pkcs8_pem(LineEnding::LF)
+            .expect("encode private key as PKCS8 PEM");
+
+        let key_pair = rcgen::KeyPair::from_pem(pem.as_str()).expect("load key pair into rcgen");
+        let mut params = rcgen::CertificateParams::new(vec!["supertokens.test".to_string()]);
+        params.alg = &rcgen::PKCS_RSA_SHA256;
+        params.key_pair = Some(key_pair);
+        let cert = rcgen::Certificate::from_params(params).expect("build certificate params");
+        let cert_der = cert.serialize_der().expect("self-sign certificate");
+
+        let jwk = Jwk {
+            alg: "RS256".to_string(),
+            kty: "RSA".to_string(),
+            key_use: "sig".to_string(),
+            kid: kid.to_string(),
+            x5c: vec![STANDARD.encode(cert_der)],
+        };
+
+        (private_key, Jwks { keys: vec![jwk] })
+    }
+
+    fn sign_token(private_key: &RsaPrivateKey, kid: &str, claims: &serde_json::Value) -> String {
+        let header = serde_json::json!({ "alg": "RS256", "kid": kid });
+        let header_b64 = URL_SAFE_NO_PAD.encode(header.to_string());
+        let payload_b64 = URL_SAFE_NO_PAD.encode(claims.to_string());
+        let signing_input = format!("{header_b64}.{payload_b64}");
+
+        let signing_key = SigningKey::<Sha256>::new(private_key.clone());
+        let signature = signing_key.sign(signing_input.as_bytes());
+        let signature_b64 = URL_SAFE_NO_PAD.encode(signature.to_bytes());
+
+        format!("{signing_input}.{signature_b64}")
+    }
+
+    fn now() -> i64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("after unix epoch")
+            .as_secs() as i64
+    }
+
+    #[tokio::test]
+    async fn accepts_a_validly_signed_token() {
+        let (key, jwks) = test_keypair_and_jwks("kid-a");
+        let claims = serde_json::json!({ "sub": "user-1", "exp": now() + 3600 });
+        let token = sign_token(&key, "kid-a", &claims);
+
+        let verified: serde_json::Value = verify_token(&jwks, &token, None).await.unwrap();
+        assert_eq!(verified["sub"], "user-1");
+    }
+
+    #[tokio::test]
+    async fn rejects_a_tampered_payload() {
+        let (key, jwks) = test_keypair_and_jwks("kid-a");
+        let claims = serde_json::json!({ "sub": "user-1", "exp": now() + 3600 });
+        let token = sign_token(&key, "kid-a", &claims);
+
+        let mut parts: Vec<&str> = token.split('.').collect();
+        let tampered_payload =
+            URL_SAFE_NO_PAD.encode(r#"{"sub":"attacker","exp":9999999999}"#);
+        parts[1] = &tampered_payload;
+        let tampered = parts.join(".");
+
+        let result = verify_token::<serde_json::Value>(&jwks, &tampered, None).await;
+        assert!(matches!(result, Err(JwtVerificationError::BadSignature)));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_expired_token() {
+        let (key, jwks) = test_keypair_and_jwks("kid-a");
+        let claims = serde_json::json!({ "sub": "user-1", "exp": now() - 3600 });
+        let token = sign_token(&key, "kid-a", &claims);
 
-    /// The format of the response did not match the
-    /// `Jwks` struct
-    ResponseFormat,
+        let result = verify_token::<serde_json::Value>(&jwks, &token, None).await;
+        assert!(matches!(result, Err(JwtVerificationError::Expired)));
+    }
+
+    #[tokio::test]
+    async fn rejects_alg_none() {
+        let (_key, jwks) = test_keypair_and_jwks("kid-a");
+        let header_b64 = URL_SAFE_NO_PAD.encode(r#"{"alg":"none","kid":"kid-a"}"#);
+        let payload_b64 = URL_SAFE_NO_PAD.encode(r#"{"sub":"user-1"}"#);
+        let token = format!("{header_b64}.{payload_b64}.");
+
+        let result = verify_token::<serde_json::Value>(&jwks, &token, None).await;
+        assert!(matches!(
+            result,
+            Err(JwtVerificationError::UnsupportedAlgorithm)
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_an_unknown_kid() {
+        let (key, jwks) = test_keypair_and_jwks("kid-a");
+        let claims = serde_json::json!({ "sub": "user-1", "exp": now() + 3600 });
+        let token = sign_token(&key, "kid-b", &claims);
 
-    /// Unknown error origin
-    Unknown,
+        let result = verify_token::<serde_json::Value>(&jwks, &token, None).await;
+        assert!(matches!(result, Err(JwtVerificationError::KeyNotFound)));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_mismatched_issuer() {
+        let (key, jwks) = test_keypair_and_jwks("kid-a");
+        let claims = serde_json::json!({
+            "sub": "user-1",
+            "exp": now() + 3600,
+            "iss": "https://issuer-a",
+        });
+        let token = sign_token(&key, "kid-a", &claims);
+
+        let result =
+            verify_token::<serde_json::Value>(&jwks, &token, Some("https://issuer-b")).await;
+        assert!(matches!(result, Err(JwtVerificationError::IssuerMismatch)));
+    }
 }
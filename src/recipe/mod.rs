@@ -0,0 +1,4 @@
+pub mod discovery;
+pub mod email_password;
+pub mod jwt;
+pub mod session;
@@ -0,0 +1,213 @@
+use crate::constants::{
+    ENDPOINT_SESSION, ENDPOINT_SESSION_REFRESH, ENDPOINT_SESSION_REMOVE, REFRESH_TOKEN_BYTES,
+};
+use crate::error::{ensure_ok, Error};
+use crate::{Recipe, SuperTokens};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+/// Starts a new login session for `user_id` via the SuperTokens core: a short-lived signed
+/// access JWT carrying `payload`, paired with a long-lived opaque refresh token, plus the
+/// `session_handle` the core tracks the session under.
+///
+/// The core normally mints the refresh token itself; one is only generated locally (as
+/// [`REFRESH_TOKEN_BYTES`](crate::constants::REFRESH_TOKEN_BYTES) bytes of CSPRNG output,
+/// base64url-encoded) if its response omits it.
+pub async fn create_session<T: Serialize>(
+    st: &SuperTokens,
+    user_id: &str,
+    payload: T,
+) -> Result<Session, Error> {
+    let resp = st
+        .client
+        .post(st.get_url_with_tenant(ENDPOINT_SESSION)?)
+        .headers(st.get_headers(Some(Recipe::Session))?)
+        .json(&CreateSessionRequest::new(user_id, payload))
+        .send()
+        .await
+        .map_err(Error::Network)?;
+
+    let resp = ensure_ok(resp).await?;
+
+    let json = resp
+        .json::<CreateSessionResponseRaw>()
+        .await
+        .map_err(Error::Deserialization)?;
+
+    if json.status != "OK" {
+        return Err(Error::CoreNonOk(json.status));
+    }
+
+    Ok(Session {
+        user_id: user_id.to_string(),
+        session_handle: json.session.ok_or(Error::MalformedResponse)?.handle,
+        access_token: json.access_token.ok_or(Error::MalformedResponse)?.token,
+        refresh_token: json
+            .refresh_token
+            .map(|token| token.token)
+            .unwrap_or_else(generate_refresh_token),
+    })
+}
+
+/// Rotates `refresh_token` via the SuperTokens core, returning the new access/refresh token pair.
+///
+/// The core invalidates the given refresh token as part of rotation, so callers must discard it
+/// in favor of [`RefreshedSession::refresh_token`] once this returns successfully. Returns
+/// [`Error::UnauthorizedSession`] if the core has already invalidated `refresh_token`, e.g. via a
+/// prior rotation or revoke.
+pub async fn refresh_session(
+    st: &SuperTokens,
+    refresh_token: &str,
+) -> Result<RefreshedSession, Error> {
+    let resp = st
+        .client
+        .post(st.get_url_with_tenant(ENDPOINT_SESSION_REFRESH)?)
+        .headers(st.get_headers(Some(Recipe::Session))?)
+        .json(&SessionRefreshRequest {
+            refresh_token: refresh_token.to_string(),
+        })
+        .send()
+        .await
+        .map_err(Error::Network)?;
+
+    let resp = ensure_ok(resp).await?;
+
+    let json = resp
+        .json::<SessionRefreshResponseRaw>()
+        .await
+        .map_err(Error::Deserialization)?;
+
+    if json.status == "UNAUTHORISED" {
+        return Err(Error::UnauthorizedSession);
+    }
+    if json.status != "OK" {
+        return Err(Error::CoreNonOk(json.status));
+    }
+
+    Ok(RefreshedSession {
+        access_token: json.access_token.ok_or(Error::MalformedResponse)?,
+        refresh_token: json.refresh_token.ok_or(Error::MalformedResponse)?,
+    })
+}
+
+/// Revokes a session by its `session_handle`, so any refresh token tied to it stops working.
+pub async fn revoke_session(st: &SuperTokens, session_handle: &str) -> Result<(), Error> {
+    let resp = st
+        .client
+        .post(st.get_url_with_tenant(ENDPOINT_SESSION_REMOVE)?)
+        .headers(st.get_headers(Some(Recipe::Session))?)
+        .json(&RevokeSessionRequest {
+            session_handles: vec![session_handle.to_string()],
+        })
+        .send()
+        .await
+        .map_err(Error::Network)?;
+
+    let resp = ensure_ok(resp).await?;
+
+    let json = resp
+        .json::<RevokeSessionResponseRaw>()
+        .await
+        .map_err(Error::Deserialization)?;
+
+    if json.status == "UNAUTHORISED" {
+        return Err(Error::UnauthorizedSession);
+    }
+    if json.status != "OK" {
+        return Err(Error::CoreNonOk(json.status));
+    }
+
+    Ok(())
+}
+
+fn generate_refresh_token() -> String {
+    let mut bytes = [0u8; REFRESH_TOKEN_BYTES];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// A freshly created login session.
+pub struct Session {
+    pub user_id: String,
+
+    /// The handle the core tracks this session under, passed to [`revoke_session`].
+    pub session_handle: String,
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+/// The new access/refresh token pair returned after rotating a session's refresh token.
+pub struct RefreshedSession {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateSessionRequest<T> {
+    user_id: String,
+    user_data_in_jwt: T,
+    user_data_in_database: serde_json::Value,
+    enable_anti_csrf: bool,
+    use_static_signing_key: bool,
+}
+
+impl<T> CreateSessionRequest<T> {
+    fn new(user_id: &str, user_data_in_jwt: T) -> Self {
+        Self {
+            user_id: user_id.to_string(),
+            user_data_in_jwt,
+            user_data_in_database: serde_json::Value::Object(Default::default()),
+            enable_anti_csrf: false,
+            use_static_signing_key: true,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateSessionResponseRaw {
+    status: String,
+    session: Option<SessionHandleRaw>,
+    access_token: Option<SessionTokenRaw>,
+    refresh_token: Option<SessionTokenRaw>,
+}
+
+#[derive(Deserialize)]
+struct SessionHandleRaw {
+    handle: String,
+}
+
+#[derive(Deserialize)]
+struct SessionTokenRaw {
+    token: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionRefreshRequest {
+    refresh_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionRefreshResponseRaw {
+    status: String,
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RevokeSessionRequest {
+    session_handles: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RevokeSessionResponseRaw {
+    status: String,
+}